@@ -1,6 +1,7 @@
 use crate::error::*;
 use crate::pty::Pty as _;
 
+#[cfg(unix)]
 use ::std::os::unix::io::AsRawFd as _;
 
 #[cfg(any(feature = "backend-async-std", feature = "backend-smol"))]
@@ -9,6 +10,8 @@ mod async_process;
 mod std;
 #[cfg(feature = "backend-tokio")]
 mod tokio;
+#[cfg(windows)]
+mod windows;
 
 /// Adds methods to the existing `Command` struct.
 ///
@@ -17,16 +20,107 @@ mod tokio;
 pub trait Command {
     type Child;
     type Pty;
+    type Stderr;
+    type ResizeNotify;
 
     /// Creates a new pty, associates the command's stdin/stdout/stderr with
     /// that pty, and then calls `spawn`. This will override any previous
     /// calls to `stdin`/`stdout`/`stderr`.
+    ///
+    /// This is a convenience wrapper around
+    /// [`spawn_pty_with`](Command::spawn_pty_with) for the common case of
+    /// not needing to configure the pty's terminal attributes, request a
+    /// separate stderr stream, or any of the other options in
+    /// [`Options`] - the returned `Child`'s `stderr` will always be
+    /// `None`. Use `spawn_pty_with` directly for those.
     fn spawn_pty(
         &mut self,
         size: Option<&crate::pty::Size>,
-    ) -> Result<Child<Self::Child, Self::Pty>>;
+    ) -> Result<
+        Child<Self::Child, Self::Pty, Self::Stderr, Self::ResizeNotify>,
+    > {
+        self.spawn_pty_with(Options {
+            size,
+            ..Options::default()
+        })
+    }
+
+    /// Like [`spawn_pty`](Command::spawn_pty), but takes an [`Options`]
+    /// struct so that callers can additionally apply terminal attributes
+    /// to the pty before the child is spawned, request a separate stderr
+    /// stream instead of having it merged into the pty, and/or
+    /// `SIGWINCH`-driven resize tracking - see [`Options`].
+    fn spawn_pty_with(
+        &mut self,
+        options: Options<'_>,
+    ) -> Result<
+        Child<Self::Child, Self::Pty, Self::Stderr, Self::ResizeNotify>,
+    >;
+}
+
+/// Options controlling how [`Command::spawn_pty_with`] sets up the pty.
+#[derive(Clone, Copy, Debug)]
+pub struct Options<'a> {
+    /// The initial size of the pty, if any.
+    pub size: Option<&'a crate::pty::Size>,
+
+    /// Terminal attributes to apply to the pty's slave side before the
+    /// child is spawned, if any - see [`PtyConfig`].
+    pub config: Option<&'a PtyConfig>,
+
+    /// If `true`, the child's stderr is routed to an independent pipe
+    /// instead of being merged into the pty along with stdout, and is
+    /// made available via [`Child::stderr`].
+    pub separate_stderr: bool,
+
+    /// If `true`, installs a `SIGWINCH` handler in the parent that writes
+    /// to a self-pipe each time the parent terminal is resized, and makes
+    /// the read end of that pipe available via
+    /// [`Child::resize_notifications`]. The handler does not resize the
+    /// pty itself: the pty is resized to the controlling terminal's
+    /// current size (read via `TIOCGWINSZ`) only when
+    /// [`Child::apply_pending_resize`] is called.
+    ///
+    /// Only one `Child` per process may set this at a time, since it's
+    /// backed by a single process-wide `SIGWINCH` handler - spawning a
+    /// second one while an earlier tracking `Child` is still alive fails
+    /// with [`Error::ResizeTrackingAlreadyActive`].
+    pub track_parent_resize: bool,
+
+    /// If `true` (the default), the child calls `setsid()` to start a
+    /// new session and makes the pts its controlling terminal.
+    ///
+    /// Set this to `false` when the pty is only wanted as an i/o channel
+    /// - e.g. when spawning inside a session or namespace that should
+    /// keep its existing controlling terminal, or that disallows
+    /// creating new sessions. The pts is still wired up as the child's
+    /// stdin/stdout (and stderr, unless `separate_stderr` is set) either
+    /// way.
+    pub session_leader: bool,
+}
+
+impl<'a> Options<'a> {
+    /// Creates an `Options` with no size or config set, stderr merged
+    /// into the pty, no parent-resize tracking, and the child becoming
+    /// a session leader with the pts as its controlling terminal.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<'a> Default for Options<'a> {
+    fn default() -> Self {
+        Self {
+            size: None,
+            config: None,
+            separate_stderr: false,
+            track_parent_resize: false,
+            session_leader: true,
+        }
+    }
 }
 
+#[cfg(unix)]
 impl<T> Command for T
 where
     T: CommandImpl,
@@ -36,27 +130,39 @@ where
 {
     type Child = T::Child;
     type Pty = T::Pty;
+    type Stderr = T::Stderr;
+    type ResizeNotify = T::ResizeNotify;
 
-    fn spawn_pty(
+    fn spawn_pty_with(
         &mut self,
-        size: Option<&crate::pty::Size>,
-    ) -> Result<Child<Self::Child, Self::Pty>> {
-        let (pty, pts, stdin, stdout, stderr) = setup_pty::<Self::Pty>(size)?;
-        
+        options: Options<'_>,
+    ) -> Result<
+        Child<Self::Child, Self::Pty, Self::Stderr, Self::ResizeNotify>,
+    > {
+        let (pty, pts, stdin, stdout, stderr, stderr_read) =
+            setup_pty::<Self::Pty>(
+                options.size,
+                options.config,
+                options.separate_stderr,
+            )?;
+
         println!("Setup pty");
 
         let pt_fd = pty.pt().as_raw_fd();
         let pts_fd = pts.as_raw_fd();
-        
+
         println!("Got fds");
 
         self.std_fds(stdin, stdout, stderr);
 
+        let session_leader = options.session_leader;
         let pre_exec = move || {
             println!("Started pre-exec");
-            nix::unistd::setsid().map_err(|e| e.as_errno().unwrap())?;
-            set_controlling_terminal(pts_fd)
-                .map_err(|e| e.as_errno().unwrap())?;
+            if session_leader {
+                nix::unistd::setsid().map_err(|e| e.as_errno().unwrap())?;
+                set_controlling_terminal(pts_fd)
+                    .map_err(|e| e.as_errno().unwrap())?;
+            }
 
             // in the parent, destructors will handle closing these file
             // descriptors (other than pt, used by the parent to
@@ -85,17 +191,92 @@ where
 
         let child = self.spawn_impl().map_err(Error::Spawn)?;
 
-        Ok(Child { child, pty })
+        let stderr = match stderr_read {
+            Some(fd) => Some(self.wrap_stderr_impl(fd)),
+            None => None,
+        };
+
+        // note: the handler is installed after the child has already been
+        // spawned above, so a `SIGWINCH` delivered in between is missed;
+        // callers that care about the exact initial size should resize
+        // explicitly via `resize_pty` right after `spawn_pty_with` returns.
+        let (resize_notify, resize_guard) = if options.track_parent_resize {
+            let (fd, guard) = install_sigwinch_self_pipe()?;
+            (Some(self.wrap_resize_notify_impl(fd)), Some(guard))
+        } else {
+            (None, None)
+        };
+
+        Ok(Child {
+            child,
+            pty,
+            stderr,
+            resize_notify,
+            resize_guard,
+        })
+    }
+}
+
+#[cfg(windows)]
+impl<T> Command for T
+where
+    T: CommandImpl,
+    T::Pty: crate::pty::Pty,
+    <<T as CommandImpl>::Pty as crate::pty::Pty>::Pt:
+        ::std::os::windows::io::AsRawHandle,
+{
+    type Child = T::Child;
+    type Pty = T::Pty;
+    type Stderr = T::Stderr;
+    type ResizeNotify = T::ResizeNotify;
+
+    fn spawn_pty_with(
+        &mut self,
+        options: Options<'_>,
+    ) -> Result<
+        Child<Self::Child, Self::Pty, Self::Stderr, Self::ResizeNotify>,
+    > {
+        // XXX PtyConfig only knows how to configure termios attributes on
+        // the slave side of a unix pty today, the ConPTY backend doesn't
+        // support splitting stderr out of the console stream yet, SIGWINCH
+        // doesn't exist on Windows, and ConPTY has no notion of sessions or
+        // controlling terminals, so `options.config`,
+        // `options.separate_stderr`, `options.track_parent_resize`, and
+        // `options.session_leader` are all ignored here.
+        let (pty, pc) = windows::setup_pty::<Self::Pty>(options.size)?;
+
+        // unlike the unix backend, ConPTY doesn't work by handing the
+        // child raw stdin/stdout/stderr handles - the pseudoconsole
+        // attribute on the process thread attribute list causes the
+        // console subsystem to wire up the child's console i/o to the
+        // pipes we gave `CreatePseudoConsole` above.
+        self.attach_pseudo_console_impl(pc);
+
+        let child = self.spawn_impl().map_err(Error::Spawn)?;
+
+        Ok(Child {
+            child,
+            pty,
+            stderr: None,
+            resize_notify: None,
+        })
     }
 }
 
 /// Wrapper struct adding pty methods to the normal `Child` struct.
-pub struct Child<C, P> {
+pub struct Child<C, P, S, R> {
     child: C,
     pty: P,
+    stderr: Option<S>,
+    resize_notify: Option<R>,
+    /// Releases the process-wide `SIGWINCH` tracking slot (see
+    /// [`Options::track_parent_resize`]) when this `Child` is dropped, so
+    /// a later `Child` can install its own tracker.
+    #[cfg(unix)]
+    resize_guard: Option<ResizeTrackingGuard>,
 }
 
-impl<C, P> Child<C, P>
+impl<C, P, S, R> Child<C, P, S, R>
 where
     P: crate::pty::Pty,
 {
@@ -128,9 +309,89 @@ where
     pub fn resize_pty(&self, size: &crate::pty::Size) -> Result<()> {
         self.pty.resize(size)
     }
+
+    /// Returns a reference to the child's stderr, if it was spawned with
+    /// [`Options::separate_stderr`] set to `true`.
+    pub fn stderr(&self) -> Option<&S> {
+        self.stderr.as_ref()
+    }
+
+    /// Returns a mutable reference to the child's stderr, if it was
+    /// spawned with [`Options::separate_stderr`] set to `true`.
+    pub fn stderr_mut(&mut self) -> Option<&mut S> {
+        self.stderr.as_mut()
+    }
+
+    /// Returns a handle for observing `SIGWINCH`-triggered resize
+    /// notifications, if it was spawned with
+    /// [`Options::track_parent_resize`] set to `true`.
+    ///
+    /// The handle becomes readable each time the parent terminal's size
+    /// changes; pass it to [`apply_pending_resize`](Child::apply_pending_resize)
+    /// once it does (e.g. after `.await`ing it through the backend's
+    /// `AsyncRead` impl) to resize the pty to match.
+    pub fn resize_notifications(&mut self) -> Option<&mut R> {
+        self.resize_notify.as_mut()
+    }
 }
 
-impl<C, P> ::std::ops::Deref for Child<C, P> {
+#[cfg(unix)]
+impl<C, P, S, R> Child<C, P, S, R>
+where
+    P: crate::pty::Pty,
+    P::Pt: ::std::os::unix::io::AsRawFd,
+{
+    /// Re-applies a [`PtyConfig`] to the already-running pty.
+    ///
+    /// Unlike the config passed to `spawn_pty_with`, which is applied to the
+    /// slave side before the child starts reading, this acts on the live
+    /// pty - useful for toggling raw mode on and off around interactive
+    /// prompts without restarting the child.
+    pub fn set_pty_config(&self, config: &PtyConfig) -> Result<()> {
+        config.apply_to(self.pty.pt().as_raw_fd())
+    }
+}
+
+#[cfg(unix)]
+impl<C, P, S, R> Child<C, P, S, R>
+where
+    P: crate::pty::Pty,
+    R: ::std::os::unix::io::AsRawFd,
+{
+    /// Drains any pending `SIGWINCH` notifications queued on the handle
+    /// returned by [`resize_notifications`](Child::resize_notifications),
+    /// and if any were pending, resizes the pty to match the parent
+    /// terminal's current size.
+    ///
+    /// Returns `Ok(None)` if [`Options::track_parent_resize`] wasn't set,
+    /// or if it was but no notification was pending.
+    pub fn apply_pending_resize(&self) -> Result<Option<crate::pty::Size>> {
+        let Some(notify) = &self.resize_notify else {
+            return Ok(None);
+        };
+        let fd = notify.as_raw_fd();
+
+        let mut buf = [0u8; 64];
+        let mut notified = false;
+        loop {
+            match nix::unistd::read(fd, &mut buf) {
+                Ok(0) => break,
+                Ok(_) => notified = true,
+                Err(nix::errno::Errno::EAGAIN) => break,
+                Err(e) => return Err(Error::SpawnNix(e)),
+            }
+        }
+        if !notified {
+            return Ok(None);
+        }
+
+        let size = parent_terminal_size()?;
+        self.pty.resize(&size)?;
+        Ok(Some(size))
+    }
+}
+
+impl<C, P, S, R> ::std::ops::Deref for Child<C, P, S, R> {
     type Target = C;
 
     fn deref(&self) -> &Self::Target {
@@ -138,16 +399,115 @@ impl<C, P> ::std::ops::Deref for Child<C, P> {
     }
 }
 
-impl<C, P> ::std::ops::DerefMut for Child<C, P> {
+impl<C, P, S, R> ::std::ops::DerefMut for Child<C, P, S, R> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.child
     }
 }
 
+/// Builder for the terminal attributes applied to the slave side of a
+/// pty before the child process is spawned.
+///
+/// Fields left unset are not touched, so the slave keeps whatever
+/// defaults the kernel gives a freshly-opened pty. Pass the result to
+/// [`Command::spawn_pty_with`] via [`Options::config`], or to
+/// [`Child::set_pty_config`] to re-apply changes to an already-running
+/// pty.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PtyConfig {
+    raw: bool,
+    echo: Option<bool>,
+    canonical: Option<bool>,
+    signal: Option<bool>,
+    control_chars: Option<(u8, u8)>,
+}
+
+impl PtyConfig {
+    /// Creates a config which makes no changes until builder methods are
+    /// called on it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Puts the pty into raw mode (equivalent to `cfmakeraw`), disabling
+    /// input/output processing, echo, and canonical mode all at once.
+    pub fn raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// Controls whether input characters are echoed back to the pty.
+    pub fn echo(mut self, echo: bool) -> Self {
+        self.echo = Some(echo);
+        self
+    }
+
+    /// Controls canonical (line-buffered) mode, i.e. `ICANON`.
+    pub fn canonical(mut self, canonical: bool) -> Self {
+        self.canonical = Some(canonical);
+        self
+    }
+
+    /// Controls whether control characters generate signals, i.e.
+    /// `ISIG`.
+    pub fn signal(mut self, signal: bool) -> Self {
+        self.signal = Some(signal);
+        self
+    }
+
+    /// Sets the `VMIN`/`VTIME` special characters, which control how
+    /// `read` blocks in non-canonical mode.
+    pub fn control_chars(mut self, vmin: u8, vtime: u8) -> Self {
+        self.control_chars = Some((vmin, vtime));
+        self
+    }
+}
+
+#[cfg(unix)]
+impl PtyConfig {
+    fn apply_to(&self, fd: ::std::os::unix::io::RawFd) -> Result<()> {
+        use nix::sys::termios::{LocalFlags, SpecialCharacterIndices};
+
+        let mut termios =
+            nix::sys::termios::tcgetattr(fd).map_err(Error::SpawnNix)?;
+
+        if self.raw {
+            nix::sys::termios::cfmakeraw(&mut termios);
+        }
+        if let Some(echo) = self.echo {
+            termios.local_flags.set(LocalFlags::ECHO, echo);
+        }
+        if let Some(canonical) = self.canonical {
+            termios.local_flags.set(LocalFlags::ICANON, canonical);
+        }
+        if let Some(signal) = self.signal {
+            termios.local_flags.set(LocalFlags::ISIG, signal);
+        }
+        if let Some((vmin, vtime)) = self.control_chars {
+            termios.control_chars[SpecialCharacterIndices::VMIN as usize] =
+                vmin;
+            termios.control_chars
+                [SpecialCharacterIndices::VTIME as usize] = vtime;
+        }
+
+        nix::sys::termios::tcsetattr(
+            fd,
+            nix::sys::termios::SetArg::TCSANOW,
+            &termios,
+        )
+        .map_err(Error::SpawnNix)?;
+
+        Ok(())
+    }
+}
+
 // XXX shouldn't be pub?
+#[cfg(unix)]
 pub trait CommandImpl {
     type Child;
     type Pty;
+    type Stderr;
+    type ResizeNotify;
 
     fn std_fds(
         &mut self,
@@ -159,16 +519,53 @@ pub trait CommandImpl {
     where
         F: FnMut() -> ::std::io::Result<()> + Send + Sync + 'static;
     fn spawn_impl(&mut self) -> ::std::io::Result<Self::Child>;
+
+    /// Wraps the read end of the separate stderr pipe (see
+    /// [`Options::separate_stderr`]) in whatever handle type this
+    /// backend exposes for reading, analogous to how `Self::Pty` wraps
+    /// the pty's master fd.
+    fn wrap_stderr_impl(
+        &mut self,
+        stderr: ::std::os::unix::io::RawFd,
+    ) -> Self::Stderr;
+
+    /// Wraps the read end of the `SIGWINCH` self-pipe (see
+    /// [`Options::track_parent_resize`]) in whatever handle type this
+    /// backend exposes for reading.
+    fn wrap_resize_notify_impl(
+        &mut self,
+        resize_notify: ::std::os::unix::io::RawFd,
+    ) -> Self::ResizeNotify;
 }
 
+// XXX shouldn't be pub?
+#[cfg(windows)]
+pub trait CommandImpl {
+    type Child;
+    type Pty;
+    type Stderr;
+    type ResizeNotify;
+
+    /// Attaches `pc` to the child process via the
+    /// `PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE` attribute passed to
+    /// `CreateProcessW`, in place of the usual `STARTF_USESTDHANDLES`
+    /// wiring.
+    fn attach_pseudo_console_impl(&mut self, pc: windows::HPCON);
+    fn spawn_impl(&mut self) -> ::std::io::Result<Self::Child>;
+}
+
+#[cfg(unix)]
 fn setup_pty<P>(
     size: Option<&crate::pty::Size>,
+    config: Option<&PtyConfig>,
+    separate_stderr: bool,
 ) -> Result<(
     P,
     ::std::fs::File,
     ::std::os::unix::io::RawFd,
     ::std::os::unix::io::RawFd,
     ::std::os::unix::io::RawFd,
+    Option<::std::os::unix::io::RawFd>,
 )>
 where
     P: crate::pty::Pty,
@@ -181,13 +578,30 @@ where
     let pts = pty.pts()?;
     let pts_fd = pts.as_raw_fd();
 
+    if let Some(config) = config {
+        config.apply_to(pts_fd)?;
+    }
+
     let stdin = nix::unistd::dup(pts_fd).map_err(Error::SpawnNix)?;
     let stdout = nix::unistd::dup(pts_fd).map_err(Error::SpawnNix)?;
-    let stderr = nix::unistd::dup(pts_fd).map_err(Error::SpawnNix)?;
 
-    Ok((pty, pts, stdin, stdout, stderr))
+    let (stderr, stderr_read) = if separate_stderr {
+        // O_CLOEXEC so the read end (kept open by the parent to receive
+        // diagnostics) doesn't leak into the child - the write end still
+        // needs its own close-on-exec flag cleared since it's meant to
+        // become the child's fd 2, which `std_fds`/`pre_exec_impl` handle
+        // the same way they do for the merged-stderr case.
+        let (read, write) = nix::unistd::pipe2(nix::fcntl::OFlag::O_CLOEXEC)
+            .map_err(Error::SpawnNix)?;
+        (write, Some(read))
+    } else {
+        (nix::unistd::dup(pts_fd).map_err(Error::SpawnNix)?, None)
+    };
+
+    Ok((pty, pts, stdin, stdout, stderr, stderr_read))
 }
 
+#[cfg(unix)]
 fn set_controlling_terminal(
     fd: ::std::os::unix::io::RawFd,
 ) -> nix::Result<()> {
@@ -197,8 +611,184 @@ fn set_controlling_terminal(
         .map(|_| ())
 }
 
+#[cfg(unix)]
 nix::ioctl_write_ptr_bad!(
     set_controlling_terminal_unsafe,
     libc::TIOCSCTTY,
     libc::c_int
 );
+
+// holds the write end of the self-pipe installed by
+// `install_sigwinch_self_pipe`, so the (async-signal-safe) signal handler
+// below can reach it; only one `Child` per process may track parent
+// resizes at a time, since `SIGWINCH` has exactly one handler process-wide
+#[cfg(unix)]
+static RESIZE_SELF_PIPE_WRITE: ::std::sync::atomic::AtomicI32 =
+    ::std::sync::atomic::AtomicI32::new(-1);
+
+#[cfg(unix)]
+extern "C" fn handle_sigwinch(_: libc::c_int) {
+    let fd = RESIZE_SELF_PIPE_WRITE
+        .load(::std::sync::atomic::Ordering::Relaxed);
+    if fd >= 0 {
+        // write(2) is async-signal-safe; ignore errors (e.g. a full
+        // pipe just means a notification is already pending)
+        let _ = nix::unistd::write(fd, &[0u8]);
+    }
+}
+
+// releases the process-wide `SIGWINCH` tracking slot when the `Child`
+// holding it is dropped, so a later `Child` can install its own tracker;
+// see `RESIZE_SELF_PIPE_WRITE` and `install_sigwinch_self_pipe`
+#[cfg(unix)]
+struct ResizeTrackingGuard {
+    write_fd: ::std::os::unix::io::RawFd,
+    prev_action: nix::sys::signal::SigAction,
+}
+
+#[cfg(unix)]
+impl Drop for ResizeTrackingGuard {
+    fn drop(&mut self) {
+        // restore the previous handler before releasing the slot, so a
+        // `SIGWINCH` delivered in between can't write to an fd a new
+        // tracker is about to reuse
+        let _ = unsafe {
+            nix::sys::signal::sigaction(
+                nix::sys::signal::Signal::SIGWINCH,
+                &self.prev_action,
+            )
+        };
+        RESIZE_SELF_PIPE_WRITE
+            .store(-1, ::std::sync::atomic::Ordering::Relaxed);
+        let _ = nix::unistd::close(self.write_fd);
+    }
+}
+
+#[cfg(unix)]
+fn install_sigwinch_self_pipe(
+) -> Result<(::std::os::unix::io::RawFd, ResizeTrackingGuard)> {
+    let (read, write) = nix::unistd::pipe2(
+        nix::fcntl::OFlag::O_NONBLOCK | nix::fcntl::OFlag::O_CLOEXEC,
+    )
+    .map_err(Error::SpawnNix)?;
+
+    // only one `Child` per process may track parent resizes at a time,
+    // since `SIGWINCH` has exactly one handler process-wide - reject the
+    // attempt instead of silently stealing the signal from whichever
+    // `Child` installed it first. The slot is released by
+    // `ResizeTrackingGuard`'s `Drop` once that `Child` goes away.
+    if RESIZE_SELF_PIPE_WRITE
+        .compare_exchange(
+            -1,
+            write,
+            ::std::sync::atomic::Ordering::Relaxed,
+            ::std::sync::atomic::Ordering::Relaxed,
+        )
+        .is_err()
+    {
+        let _ = nix::unistd::close(read);
+        let _ = nix::unistd::close(write);
+        return Err(Error::ResizeTrackingAlreadyActive);
+    }
+
+    let action = nix::sys::signal::SigAction::new(
+        nix::sys::signal::SigHandler::Handler(handle_sigwinch),
+        nix::sys::signal::SaFlags::SA_RESTART,
+        nix::sys::signal::SigSet::empty(),
+    );
+    // safe because handle_sigwinch only calls async-signal-safe functions
+    let prev_action = match unsafe {
+        nix::sys::signal::sigaction(
+            nix::sys::signal::Signal::SIGWINCH,
+            &action,
+        )
+    } {
+        Ok(prev_action) => prev_action,
+        Err(e) => {
+            RESIZE_SELF_PIPE_WRITE
+                .store(-1, ::std::sync::atomic::Ordering::Relaxed);
+            let _ = nix::unistd::close(read);
+            let _ = nix::unistd::close(write);
+            return Err(Error::SpawnNix(e));
+        }
+    };
+
+    Ok((
+        read,
+        ResizeTrackingGuard {
+            write_fd: write,
+            prev_action,
+        },
+    ))
+}
+
+#[cfg(unix)]
+fn parent_terminal_size() -> Result<crate::pty::Size> {
+    let tty = ::std::fs::File::open("/dev/tty").map_err(Error::Spawn)?;
+    let mut winsize: libc::winsize = unsafe { ::std::mem::zeroed() };
+    unsafe { terminal_size_unsafe(tty.as_raw_fd(), &mut winsize) }
+        .map_err(Error::SpawnNix)?;
+    Ok(crate::pty::Size {
+        row: winsize.ws_row,
+        col: winsize.ws_col,
+    })
+}
+
+#[cfg(unix)]
+nix::ioctl_read_bad!(terminal_size_unsafe, libc::TIOCGWINSZ, libc::winsize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pty_config_builder_sets_fields() {
+        let config = PtyConfig::new()
+            .raw(true)
+            .echo(false)
+            .canonical(false)
+            .signal(true)
+            .control_chars(1, 0);
+        assert!(config.raw);
+        assert_eq!(config.echo, Some(false));
+        assert_eq!(config.canonical, Some(false));
+        assert_eq!(config.signal, Some(true));
+        assert_eq!(config.control_chars, Some((1, 0)));
+    }
+
+    #[test]
+    fn options_default_merges_stderr_into_pty() {
+        let options = Options::default();
+        assert!(!options.separate_stderr);
+    }
+
+    // a second tracker installed while the first is still alive must be
+    // rejected, and dropping the first must free the slot for a third -
+    // this is the scenario that two sequentially-spawned, tracked
+    // `Child`s go through over a long-running process's lifetime.
+    #[test]
+    #[cfg(unix)]
+    fn resize_tracking_guard_releases_slot_on_drop() {
+        let (first_read, first_guard) =
+            install_sigwinch_self_pipe().unwrap();
+
+        assert!(matches!(
+            install_sigwinch_self_pipe(),
+            Err(Error::ResizeTrackingAlreadyActive)
+        ));
+
+        drop(first_guard);
+        let _ = nix::unistd::close(first_read);
+
+        let (second_read, second_guard) =
+            install_sigwinch_self_pipe().unwrap();
+        drop(second_guard);
+        let _ = nix::unistd::close(second_read);
+    }
+
+    #[test]
+    fn options_default_is_session_leader() {
+        let options = Options::default();
+        assert!(options.session_leader);
+    }
+}
@@ -0,0 +1,90 @@
+//! ConPTY-backed pty setup for the Windows platform.
+//!
+//! This mirrors `setup_pty` in the parent module, but Windows has no
+//! notion of a pts fd to dup onto stdin/stdout/stderr - instead a
+//! pseudoconsole is created over a pair of anonymous pipes, and attached
+//! to the child process via a process thread attribute rather than by
+//! reassigning standard handles.
+//!
+//! The pseudoconsole handle itself is owned by the `Pty` implementation
+//! (see `crate::pty`), which is responsible for calling
+//! `ResizePseudoConsole`/`ClosePseudoConsole` from `resize`/`Drop`; this
+//! module only deals with creating it and wiring up the pipes.
+
+use crate::error::*;
+
+use ::std::ptr;
+
+pub use winapi::um::wincontypes::HPCON;
+
+use winapi::shared::winerror::S_OK;
+use winapi::um::consoleapi::CreatePseudoConsole;
+use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+use winapi::um::namedpipeapi::CreatePipe;
+use winapi::um::wincontypes::COORD;
+
+pub(crate) fn setup_pty<P>(
+    size: Option<&crate::pty::Size>,
+) -> Result<(P, HPCON)>
+where
+    P: crate::pty::Pty,
+{
+    let pty = P::new()?;
+
+    let (console_in_read, console_in_write) = create_pipe()?;
+    let (console_out_read, console_out_write) = create_pipe()?;
+
+    // the pty keeps the ends of the pipes that the parent reads from and
+    // writes to; the other ends are handed to the pseudoconsole, which
+    // forwards them to the child's console.
+    pty.attach(console_in_write, console_out_read)?;
+
+    let coord = size.map_or(COORD { X: 80, Y: 24 }, |size| COORD {
+        X: size.col as i16,
+        Y: size.row as i16,
+    });
+
+    let mut hpcon: HPCON = ptr::null_mut();
+    let hr = unsafe {
+        CreatePseudoConsole(
+            coord,
+            console_in_read,
+            console_out_write,
+            0,
+            &mut hpcon,
+        )
+    };
+    // the pseudoconsole duplicates the handles it needs internally, so
+    // the originals can (and should) be closed now
+    unsafe {
+        CloseHandle(console_in_read);
+        CloseHandle(console_out_write);
+    }
+    if hr != S_OK {
+        return Err(Error::SpawnWindows(hr));
+    }
+
+    // hand the handle to the `Pty` so `Child::resize_pty` and the `Pty`'s
+    // `Drop` can reach it via `ResizePseudoConsole`/`ClosePseudoConsole` -
+    // `setup_pty` still returns it too, since `CreateProcessW` also needs
+    // it attached to the child via `attach_pseudo_console_impl`.
+    pty.set_pseudo_console(hpcon);
+
+    Ok((pty, hpcon))
+}
+
+fn create_pipe() -> Result<(
+    ::std::os::windows::io::RawHandle,
+    ::std::os::windows::io::RawHandle,
+)> {
+    let mut read = INVALID_HANDLE_VALUE;
+    let mut write = INVALID_HANDLE_VALUE;
+    let ok =
+        unsafe { CreatePipe(&mut read, &mut write, ptr::null_mut(), 0) };
+    if ok == 0 {
+        return Err(Error::SpawnWindows(unsafe {
+            winapi::um::errhandlingapi::GetLastError() as i32
+        }));
+    }
+    Ok((read as _, write as _))
+}